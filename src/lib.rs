@@ -1,14 +1,20 @@
 
 #![allow(dead_code)]
 
-pub struct U8RingBuffer {
-    ring: Vec<u8>,
-    buffer: Vec<u8>,
+use std::io;
+use std::ops::Index;
+
+pub struct RingBuffer<T> {
+    ring: Vec<T>,
+    buffer: Vec<T>,
     len: usize,
     pos: usize,
 }
 
-impl U8RingBuffer {
+/// The original byte-oriented instantiation, kept for source compatibility.
+pub type U8RingBuffer = RingBuffer<u8>;
+
+impl<T: Copy> RingBuffer<T> {
 
     pub fn new(capacity: usize) -> Self {
         let mut ring = Vec::with_capacity(capacity);
@@ -47,7 +53,19 @@ impl U8RingBuffer {
         self.pos = 0;
     }
 
-    pub fn push(&mut self, mut buffer: &[u8]) {
+    /// Lengths of the two physical segments that make up the logical
+    /// contents, in order: the head segment starting at `last()` and
+    /// running at most to the end of `ring`, and the wrapped tail segment
+    /// starting at index `0`. The tail length is `0` when the data is
+    /// contiguous.
+    fn data_slice_lengths(&self) -> (usize, usize) {
+        let capacity = self.capacity();
+        let last = self.last();
+        let head = self.len.min(capacity - last);
+        (head, self.len - head)
+    }
+
+    pub fn push(&mut self, mut buffer: &[T]) {
         let capacity = self.capacity();
         if buffer.len() > capacity {
             buffer = &buffer[buffer.len() - capacity..];
@@ -56,14 +74,14 @@ impl U8RingBuffer {
         if buffer.len() == capacity {
             unsafe {
                 std::ptr::copy_nonoverlapping(
-                    &buffer[0] as *const _ as *const u8,
-                    &mut self.ring[pos] as *mut _ as *mut u8,
+                    buffer.as_ptr(),
+                    self.ring.as_mut_ptr().add(pos),
                     capacity - pos,
                 );
                 if pos != 0 {
                     std::ptr::copy_nonoverlapping(
-                        &buffer[capacity - pos] as *const _ as *const u8,
-                        &mut self.ring[0] as *mut _ as *mut u8,
+                        buffer[capacity - pos..].as_ptr(),
+                        self.ring.as_mut_ptr(),
                         pos,
                     );
                 }
@@ -74,13 +92,13 @@ impl U8RingBuffer {
             if buffer.len() > capacity - pos {
                 unsafe {
                     std::ptr::copy_nonoverlapping(
-                        &buffer[0] as *const _ as *const u8,
-                        &mut self.ring[pos] as *mut _ as *mut u8,
+                        buffer.as_ptr(),
+                        self.ring.as_mut_ptr().add(pos),
                         capacity - pos,
                     );
                     std::ptr::copy_nonoverlapping(
-                        &buffer[buffer.len() - (capacity - pos) + 1] as *const _ as *const u8,
-                        &mut self.ring[0] as *mut _ as *mut u8,
+                        buffer[capacity - pos..].as_ptr(),
+                        self.ring.as_mut_ptr(),
                         buffer.len() - (capacity - pos),
                     );
                 }
@@ -88,8 +106,8 @@ impl U8RingBuffer {
             else {
                 unsafe {
                     std::ptr::copy_nonoverlapping(
-                        &buffer[0] as *const _ as *const u8,
-                        &mut self.ring[pos] as *mut _ as *mut u8,
+                        buffer.as_ptr(),
+                        self.ring.as_mut_ptr().add(pos),
                         buffer.len(),
                     )
                 }
@@ -98,7 +116,120 @@ impl U8RingBuffer {
         }
    }
 
-    pub fn slice(&mut self) -> &[u8] {
+    /// Remaining capacity that can be filled without overwriting live data.
+    pub fn free(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    /// Grows the backing store so that at least `additional` more
+    /// elements can be pushed without overwriting live data. Uses the
+    /// amortized strategy ruzstd's ring buffer uses: the new capacity is
+    /// the larger of `capacity.next_power_of_two()` and
+    /// `(capacity + additional).next_power_of_two()`. Existing contents are linearized to offset
+    /// `0` in the new store so `pos`/`last` stay simple afterwards.
+    pub fn reserve(&mut self, additional: usize) {
+        if self.free() >= additional {
+            return;
+        }
+        let capacity = self.capacity();
+        let new_cap = capacity
+            .next_power_of_two()
+            .max((capacity + additional).next_power_of_two());
+
+        let mut new_ring = Vec::with_capacity(new_cap);
+        unsafe {
+            new_ring.set_len(new_cap);
+        }
+        let (head, tail) = self.as_slices();
+        let head_len = head.len();
+        let tail_len = tail.len();
+        unsafe {
+            std::ptr::copy_nonoverlapping(head.as_ptr(), new_ring.as_mut_ptr(), head_len);
+            std::ptr::copy_nonoverlapping(
+                tail.as_ptr(),
+                new_ring.as_mut_ptr().add(head_len),
+                tail_len,
+            );
+        }
+
+        self.buffer = new_ring.clone();
+        self.ring = new_ring;
+        self.pos = self.len;
+    }
+
+    /// Non-destructive counterpart to [`RingBuffer::push`]: grows the
+    /// buffer via [`RingBuffer::reserve`] instead of overwriting the
+    /// oldest elements, so ring-vs-queue behavior is a caller choice
+    /// rather than hard-wired. `push` remains the lossy, fixed-capacity
+    /// mode.
+    pub fn try_push(&mut self, buffer: &[T]) -> bool {
+        self.reserve(buffer.len());
+        let capacity = self.capacity();
+        let pos = self.pos;
+        if buffer.len() <= capacity - pos {
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    buffer.as_ptr(),
+                    self.ring.as_mut_ptr().add(pos),
+                    buffer.len(),
+                );
+            }
+        } else {
+            let first = capacity - pos;
+            unsafe {
+                std::ptr::copy_nonoverlapping(buffer.as_ptr(), self.ring.as_mut_ptr().add(pos), first);
+                std::ptr::copy_nonoverlapping(
+                    buffer[first..].as_ptr(),
+                    self.ring.as_mut_ptr(),
+                    buffer.len() - first,
+                );
+            }
+        }
+        self.inc_pos_by(buffer.len());
+        true
+    }
+
+    /// Returns the two ring segments that make up the logical contents,
+    /// oldest first, without copying. Mirrors `VecDeque::as_slices`: the
+    /// second slice is empty unless the data wraps around the end of the
+    /// backing store.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (head_len, tail_len) = self.data_slice_lengths();
+        let last = self.last();
+        (&self.ring[last..last + head_len], &self.ring[..tail_len])
+    }
+
+    /// Mutable counterpart of [`RingBuffer::as_slices`].
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (head_len, tail_len) = self.data_slice_lengths();
+        let last = self.last();
+        let (front, back) = self.ring.split_at_mut(last);
+        (&mut back[..head_len], &mut front[..tail_len])
+    }
+
+    /// Maps a logical index (`0` = oldest live element) onto the backing
+    /// slot and returns it, without the `O(n)` `slice()` copy.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        let (head, tail) = self.as_slices();
+        if index < head.len() {
+            Some(&head[index])
+        } else {
+            Some(&tail[index - head.len()])
+        }
+    }
+
+    /// Walks the logical contents oldest-first, without the `O(n)`
+    /// `slice()` copy. Just `seg0.iter().chain(seg1.iter())` over
+    /// [`RingBuffer::as_slices`].
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let (head, tail) = self.as_slices();
+        head.iter().chain(tail.iter())
+    }
+
+    pub fn slice(&mut self) -> &[T] {
         let capacity = self.capacity();
         let last = self.last();
         let len = self.len;
@@ -106,22 +237,22 @@ impl U8RingBuffer {
         unsafe {
             if last < pos {
                 std::ptr::copy_nonoverlapping(
-                    &self.ring[last] as *const _ as *const u8,
-                    &mut self.buffer[0] as *mut _ as *mut u8,
+                    self.ring[last..].as_ptr(),
+                    self.buffer.as_mut_ptr(),
                     self.len,
                 );
                 &self.buffer[..len]
             }
             else if last > pos {
                 std::ptr::copy_nonoverlapping(
-                    &self.ring[pos] as *const _ as *const u8,
-                    &mut self.buffer[0] as *mut _ as *mut u8,
+                    self.ring[pos..].as_ptr(),
+                    self.buffer.as_mut_ptr(),
                     capacity - pos,
                 );
                 if last != capacity {
                     std::ptr::copy_nonoverlapping(
-                        &self.ring[0] as *const _ as *const u8,
-                        &mut self.buffer[capacity - last] as *mut _ as *mut u8,
+                        self.ring.as_ptr(),
+                        self.buffer[capacity - last..].as_mut_ptr(),
                         capacity - last,
                     );
                 }
@@ -129,14 +260,14 @@ impl U8RingBuffer {
             }
             else {
                 std::ptr::copy_nonoverlapping(
-                    &self.ring[pos] as *const _ as *const u8,
-                    &mut self.buffer[0] as *mut _ as *mut u8,
+                    self.ring[pos..].as_ptr(),
+                    self.buffer.as_mut_ptr(),
                     capacity - pos,
                 );
                 if pos != 0 {
                     std::ptr::copy_nonoverlapping(
-                        &self.ring[0] as *const _ as *const u8,
-                        &mut self.buffer[capacity - pos] as *mut _ as *mut u8,
+                        self.ring.as_ptr(),
+                        self.buffer[capacity - pos..].as_mut_ptr(),
                         pos,
                     );
                 }
@@ -145,6 +276,44 @@ impl U8RingBuffer {
         }
     }
 
+    /// Appends `len` elements copied from the buffer's own history,
+    /// starting at logical offset `start` (0 = oldest live element),
+    /// advancing `pos`/`len` exactly like [`RingBuffer::push`]. `start`
+    /// must refer to an element that is currently live
+    /// (`start < self.len()`).
+    ///
+    /// The match distance `self.len() - start` may be smaller than
+    /// `len`, in which case the copy reads elements that this very call
+    /// writes earlier in the loop (an LZ77-style overlapping
+    /// back-reference). Copying therefore proceeds left-to-right in
+    /// chunks bounded by that distance rather than as one
+    /// `copy_nonoverlapping`.
+    pub fn extend_from_within(&mut self, start: usize, len: usize) -> bool {
+        if start >= self.len {
+            return false;
+        }
+        let capacity = self.capacity();
+        let distance = self.len - start;
+        let mut src = (self.last() + start) % capacity;
+        let mut dst = self.pos;
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk = remaining
+                .min(capacity - src)
+                .min(capacity - dst)
+                .min(distance);
+            unsafe {
+                let base = self.ring.as_mut_ptr();
+                std::ptr::copy(base.add(src), base.add(dst), chunk);
+            }
+            src = (src + chunk) % capacity;
+            dst = (dst + chunk) % capacity;
+            remaining -= chunk;
+        }
+        self.inc_pos_by(len);
+        true
+    }
+
     fn inc_pos_by(&mut self, inc: usize) {
         let capacity = self.capacity();
         self.pos += inc;
@@ -154,25 +323,6 @@ impl U8RingBuffer {
         self.len = self.len.min(capacity);
     }
 
-    fn occurence(&mut self, buffer: &[u8], offset: usize) -> Option<usize> {
-        let slice = self.slice();
-        let blen = buffer.len();
-        let slen = slice.len();
-        if offset + blen > slen { return None }
-        for idx in offset..slen-blen {
-            if buffer == &slice[idx..idx+blen] {
-                return Some(idx)
-            }
-        }
-        None
-    }
-    pub fn first_occurence(&mut self, buffer: &[u8]) -> Option<usize> {
-        self.occurence(buffer, 0)
-    }
-    pub fn second_occurence(&mut self, buffer: &[u8]) -> Option<usize> {
-        self.occurence(buffer, 0).map(|offset| self.occurence(buffer, offset + 1)).unwrap_or(None)
-    }
-
     pub fn purge(&mut self, amount: usize) -> bool {
         if amount > self.len {
             return false;
@@ -189,10 +339,139 @@ impl U8RingBuffer {
 
 }
 
+impl<T: Copy + PartialEq> RingBuffer<T> {
+
+    /// Builds the KMP failure table for `needle`: `table[i]` is the
+    /// length of the longest proper prefix of `needle[..=i]` that is
+    /// also a suffix of it.
+    fn kmp_failure_table(needle: &[T]) -> Vec<usize> {
+        let mut table = vec![0usize; needle.len()];
+        let mut k = 0;
+        for i in 1..needle.len() {
+            while k > 0 && needle[i] != needle[k] {
+                k = table[k - 1];
+            }
+            if needle[i] == needle[k] {
+                k += 1;
+            }
+            table[i] = k;
+        }
+        table
+    }
+
+    /// Finds the first logical start index `>= from` at which `needle`
+    /// occurs, streaming the haystack elements directly out of
+    /// [`RingBuffer::as_slices`] (no `slice()` materialization) and
+    /// matching in linear time via Knuth–Morris–Pratt.
+    pub fn find(&self, needle: &[T], from: usize) -> Option<usize> {
+        let blen = needle.len();
+        if blen == 0 || self.len < blen {
+            return None;
+        }
+        let table = Self::kmp_failure_table(needle);
+        let (head, tail) = self.as_slices();
+        let mut matched = 0usize;
+        for (idx, &item) in head.iter().chain(tail.iter()).enumerate() {
+            while matched > 0 && item != needle[matched] {
+                matched = table[matched - 1];
+            }
+            if item == needle[matched] {
+                matched += 1;
+            }
+            if matched == blen {
+                let start = idx + 1 - blen;
+                if start >= from {
+                    return Some(start);
+                }
+                matched = table[matched - 1];
+            }
+        }
+        None
+    }
+
+    /// Iterates every logical start index at which `needle` occurs, in
+    /// ascending order.
+    pub fn find_all(&self, needle: &[T]) -> impl Iterator<Item = usize> {
+        let blen = needle.len();
+        let mut matches = Vec::new();
+        if blen != 0 && self.len >= blen {
+            let table = Self::kmp_failure_table(needle);
+            let (head, tail) = self.as_slices();
+            let mut matched = 0usize;
+            for (idx, &item) in head.iter().chain(tail.iter()).enumerate() {
+                while matched > 0 && item != needle[matched] {
+                    matched = table[matched - 1];
+                }
+                if item == needle[matched] {
+                    matched += 1;
+                }
+                if matched == blen {
+                    matches.push(idx + 1 - blen);
+                    matched = table[matched - 1];
+                }
+            }
+        }
+        matches.into_iter()
+    }
+
+    pub fn first_occurence(&mut self, buffer: &[T]) -> Option<usize> {
+        self.find(buffer, 0)
+    }
+    pub fn second_occurence(&mut self, buffer: &[T]) -> Option<usize> {
+        self.first_occurence(buffer).and_then(|first| self.find(buffer, first + 1))
+    }
+
+}
+
+impl<T: Copy> Index<usize> for RingBuffer<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl io::Write for RingBuffer<u8> {
+    /// Accepts the whole slice via the existing lossy `push`, overwriting
+    /// the oldest bytes once capacity is reached. The returned count is
+    /// the number of bytes actually retained (`capacity` when `buf` is
+    /// larger than the buffer).
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len().min(self.capacity()))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Read for RingBuffer<u8> {
+    /// Copies the oldest `buf.len().min(self.len())` bytes into `buf`,
+    /// reading across both ring segments, then `purge`s them. A short
+    /// read (`n < buf.len()`) simply means fewer bytes were buffered.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let to_read = buf.len().min(self.len());
+        if to_read == 0 {
+            return Ok(0);
+        }
+        let (head, tail) = self.as_slices();
+        let head_len = head.len().min(to_read);
+        buf[..head_len].copy_from_slice(&head[..head_len]);
+        let tail_read = to_read - head_len;
+        if tail_read > 0 {
+            buf[head_len..to_read].copy_from_slice(&tail[..tail_read]);
+        }
+        self.purge(to_read);
+        Ok(to_read)
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::{Read, Write};
 
     #[test]
     fn test_len() {
@@ -239,6 +518,21 @@ mod tests {
         assert!(!buffer.is_empty());
     }
 
+    #[test]
+    fn test_push_wraps_without_overflowing_input_slice() {
+        // Exercises the non-full "buffer.len() > capacity - pos" wrap
+        // branch: at this point the source offset for the second
+        // `copy_nonoverlapping` must be `capacity - pos`, not
+        // `buffer.len() - (capacity - pos) + 1`.
+        let mut buffer = U8RingBuffer::new(10);
+        buffer.push(&[1,2,3,4,5,6,7,8]);
+        assert_eq!(buffer.len(), 8);
+
+        buffer.push(&[10,11,12,13,14]);
+        assert_eq!(buffer.slice(), &[4,5,6,7,8,10,11,12,13,14]);
+        assert_eq!(buffer.len(), 10);
+    }
+
     #[test]
     fn test_edge_cases() {
         let mut buffer = U8RingBuffer::new(10);
@@ -299,6 +593,83 @@ mod tests {
         assert_eq!(buffer.purge(100), false);
     }
 
+    #[test]
+    fn test_find_all() {
+        let mut buffer = U8RingBuffer::new(20);
+        buffer.push(b"abcabcabc");
+        assert_eq!(buffer.find_all(b"abc").collect::<Vec<_>>(), vec![0, 3, 6]);
+        assert_eq!(buffer.find(b"abc", 4), Some(6));
+        assert_eq!(buffer.find(b"xyz", 0), None);
+        // the previous off-by-one in `occurence` missed a match starting
+        // at the very last possible window.
+        assert_eq!(buffer.find(b"bc", 7), Some(7));
+    }
+
+    #[test]
+    fn test_as_slices() {
+        let mut buffer = U8RingBuffer::new(10);
+        buffer.push(&[1,2,3,4,5]);
+        assert_eq!(buffer.as_slices(), (&[1,2,3,4,5][..], &[][..]));
+
+        buffer.push(&[6,7,8,9,10]);
+        assert_eq!(buffer.as_slices(), (&[1,2,3,4,5,6,7,8,9,10][..], &[][..]));
+
+        buffer.push(&[11,12,13]);
+        assert_eq!(buffer.as_slices(), (&[4,5,6,7,8,9,10][..], &[11,12,13][..]));
+
+        let (head, tail) = buffer.as_mut_slices();
+        head[0] = 40;
+        tail[0] = 11;
+        assert_eq!(buffer.slice(), &[40,5,6,7,8,9,10,11,12,13]);
+    }
+
+    #[test]
+    fn test_extend_from_within() {
+        let mut buffer = U8RingBuffer::new(10);
+        buffer.push(&[1,2,3]);
+        assert!(buffer.extend_from_within(0, 3));
+        assert_eq!(buffer.slice(), &[1,2,3,1,2,3]);
+
+        assert!(buffer.extend_from_within(5, 4));
+        assert_eq!(buffer.slice(), &[1,2,3,1,2,3,3,3,3,3]);
+        assert_eq!(buffer.len(), 10);
+
+        assert!(!buffer.extend_from_within(10, 1));
+    }
+
+    #[test]
+    fn test_try_push_grows_instead_of_overwriting() {
+        let mut buffer = U8RingBuffer::new(4);
+        buffer.push(&[1,2,3,4]);
+        buffer.push(&[5]);
+        assert_eq!(buffer.slice(), &[2,3,4,5]);
+
+        assert!(buffer.try_push(&[6,7]));
+        assert_eq!(buffer.capacity(), 8);
+        assert_eq!(buffer.len(), 6);
+        assert_eq!(buffer.slice(), &[2,3,4,5,6,7]);
+        assert_eq!(buffer.free(), 2);
+    }
+
+    #[test]
+    fn test_read_write() {
+        let mut buffer = U8RingBuffer::new(10);
+        let written = buffer.write(&[1,2,3,4,5]).unwrap();
+        assert_eq!(written, 5);
+
+        let mut out = [0u8; 3];
+        let read = buffer.read(&mut out).unwrap();
+        assert_eq!(read, 3);
+        assert_eq!(out, [1,2,3]);
+        assert_eq!(buffer.len(), 2);
+
+        let mut out = [0u8; 10];
+        let read = buffer.read(&mut out).unwrap();
+        assert_eq!(read, 2);
+        assert_eq!(&out[..2], &[4,5]);
+        assert!(buffer.is_empty());
+    }
+
     #[test]
     fn test_clean() {
         let mut buffer = U8RingBuffer::new(5);
@@ -312,4 +683,31 @@ mod tests {
         buffer.push(&[4,5,6]);
         assert_eq!(buffer.slice(), &[4, 5, 6]);
     }
+
+    #[test]
+    fn test_get_index_iter() {
+        let mut buffer = U8RingBuffer::new(4);
+        buffer.push(&[1,2,3,4]);
+        buffer.push(&[5,6]);
+        assert_eq!(buffer.slice(), &[3,4,5,6]);
+
+        assert_eq!(buffer.get(0), Some(&3));
+        assert_eq!(buffer.get(3), Some(&6));
+        assert_eq!(buffer.get(4), None);
+        assert_eq!(buffer[0], 3);
+        assert_eq!(buffer[3], 6);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3,4,5,6]);
+    }
+
+    #[test]
+    fn test_generic_element_type() {
+        let mut buffer: RingBuffer<i32> = RingBuffer::new(4);
+        buffer.push(&[1, 2, 3, 4]);
+        buffer.push(&[5, 6]);
+        assert_eq!(buffer.slice(), &[3, 4, 5, 6]);
+        assert_eq!(buffer.find(&[4, 5], 0), Some(1));
+
+        let (head, tail) = buffer.as_slices();
+        assert_eq!([head, tail].concat(), vec![3, 4, 5, 6]);
+    }
 }